@@ -0,0 +1,62 @@
+//! Demonstrates an [`AudioProcessor`]'s message port: the control thread posts gain updates and
+//! the render thread picks them up at the top of its own `process`, instead of the gain being
+//! fixed for the processor's whole lifetime.
+use std::any::Any;
+
+use web_audio_api::context::{BaseAudioContext, OfflineAudioContext};
+use web_audio_api::render::{
+    message_channel, AudioParamValues, AudioProcessor, AudioRenderQuantum, MessageReceiver,
+    MessageSender,
+};
+use web_audio_api::SampleRate;
+
+/// Scales its input by a gain factor, adjustable from the control thread through its message port
+struct GainProcessor {
+    gain: f32,
+    receiver: MessageReceiver,
+}
+
+impl AudioProcessor for GainProcessor {
+    fn process(
+        &mut self,
+        inputs: &[AudioRenderQuantum],
+        outputs: &mut [AudioRenderQuantum],
+        _params: AudioParamValues,
+        _timestamp: f64,
+        sample_rate: SampleRate,
+    ) -> bool {
+        // `try_recv` hands back an owned message, so this loop never borrows `self` twice at
+        // once - see `MessageReceiver::try_recv` for why that matters.
+        while let Some(msg) = self.receiver.try_recv() {
+            self.on_message(msg);
+        }
+
+        let scaled: Vec<Vec<f32>> = inputs[0]
+            .channels()
+            .iter()
+            .map(|channel| channel.as_slice().iter().map(|s| s * self.gain).collect())
+            .collect();
+        outputs[0] = AudioRenderQuantum::from(scaled, sample_rate);
+
+        true
+    }
+
+    fn on_message(&mut self, msg: Box<dyn Any + Send>) {
+        if let Ok(gain) = msg.downcast::<f32>() {
+            self.gain = *gain;
+        }
+    }
+}
+
+fn main() {
+    let context = OfflineAudioContext::new(1, 128, SampleRate(44_100));
+
+    let (sender, receiver) = message_channel();
+    let processor = GainProcessor { gain: 1., receiver };
+
+    let sender: MessageSender =
+        context.register(move |_registration| (sender, Box::new(processor) as Box<dyn AudioProcessor>));
+
+    // turn the gain down - `GainProcessor` applies it the next time its `process` runs
+    sender.send(Box::new(0.5_f32));
+}
@@ -0,0 +1,6 @@
+//! Render-thread buffer allocation
+//!
+//! For now this simply re-exports [`crate::buffer::AudioBuffer`]; a pooled/preallocated arena
+//! would live here once the render thread cannot afford to allocate per quantum.
+
+pub use crate::buffer::AudioBuffer;
@@ -0,0 +1,112 @@
+//! Plain multi-channel sample storage, used to move audio between the media layer, the render
+//! graph and [`crate::render::AudioProcessor`] implementations.
+
+use std::ops::{Index, RangeFull};
+
+use crate::SampleRate;
+
+/// Per-channel sample storage for an [`AudioBuffer`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChannelData(Vec<f32>);
+
+impl ChannelData {
+    pub fn as_slice(&self) -> &[f32] {
+        &self.0
+    }
+}
+
+impl From<Vec<f32>> for ChannelData {
+    fn from(data: Vec<f32>) -> Self {
+        Self(data)
+    }
+}
+
+impl Index<RangeFull> for ChannelData {
+    type Output = [f32];
+
+    fn index(&self, _: RangeFull) -> &[f32] {
+        &self.0
+    }
+}
+
+/// Options used to allocate a new, silent [`AudioBuffer`]
+#[derive(Debug, Clone, Copy)]
+pub struct AudioBufferOptions {
+    pub number_of_channels: usize,
+    pub length: usize,
+    pub sample_rate: SampleRate,
+}
+
+/// A multi-channel buffer of `f32` audio samples, tagged with its sample rate
+#[derive(Debug, Clone)]
+pub struct AudioBuffer {
+    channels: Vec<ChannelData>,
+    sample_rate: SampleRate,
+}
+
+impl AudioBuffer {
+    /// Allocate a new, silent buffer
+    pub fn new(options: AudioBufferOptions) -> Self {
+        let channels = vec![ChannelData(vec![0.; options.length]); options.number_of_channels];
+        Self {
+            channels,
+            sample_rate: options.sample_rate,
+        }
+    }
+
+    pub fn from_channels(channels: Vec<ChannelData>, sample_rate: SampleRate) -> Self {
+        Self {
+            channels,
+            sample_rate,
+        }
+    }
+
+    /// Build a buffer from one sample vector per channel (planar, not interleaved)
+    pub fn from(samples: Vec<Vec<f32>>, sample_rate: SampleRate) -> Self {
+        Self {
+            channels: samples.into_iter().map(ChannelData::from).collect(),
+            sample_rate,
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.channels.first().map_or(0, |c| c.0.len())
+    }
+
+    pub fn number_of_channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    pub fn channel_data(&self, index: usize) -> &ChannelData {
+        &self.channels[index]
+    }
+
+    pub fn channels(&self) -> &[ChannelData] {
+        &self.channels
+    }
+
+    /// Append `other`'s samples onto the end of this buffer, channel by channel
+    pub fn extend(&mut self, other: &AudioBuffer) {
+        for (channel, other_channel) in self.channels.iter_mut().zip(&other.channels) {
+            channel.0.extend_from_slice(&other_channel.0);
+        }
+    }
+
+    /// Split this buffer at sample frame `at`, keeping the first `at` frames in `self` and
+    /// returning the rest as a new buffer
+    pub fn split_off(&mut self, at: usize) -> AudioBuffer {
+        let channels = self
+            .channels
+            .iter_mut()
+            .map(|c| ChannelData(c.0.split_off(at)))
+            .collect();
+        AudioBuffer {
+            channels,
+            sample_rate: self.sample_rate,
+        }
+    }
+}
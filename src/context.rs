@@ -0,0 +1,277 @@
+//! Control-thread context types: where nodes get registered into the render graph that runs on
+//! the audio thread.
+
+use std::error::Error;
+use std::io::Read;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use intmap::IntMap;
+
+use crate::buffer::AudioBuffer;
+use crate::device::{AudioContextConfig, AudioContextDeviceOptions, MediaDeviceInfo};
+use crate::graph::{Node, NodeIndex};
+use crate::node::{ChannelConfigOptions, MediaStreamAudioDestinationNode};
+use crate::render::AudioProcessor;
+use crate::SampleRate;
+
+static NEXT_REGISTRATION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Default sample rate used when a context is not constructed against a specific device
+const DEFAULT_SAMPLE_RATE: SampleRate = SampleRate(44_100);
+
+/// Identifies a node's registration with a [`BaseAudioContext`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioContextRegistration {
+    id: u64,
+}
+
+/// Identifies an audio parameter's backing node in the render graph, as looked up by
+/// [`crate::render::AudioParamValues::get`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioParamId(u64);
+
+impl From<&AudioParamId> for NodeIndex {
+    fn from(id: &AudioParamId) -> Self {
+        NodeIndex(id.0)
+    }
+}
+
+/// The render graph shared between a context and its render thread: every registered processor
+pub(crate) struct RenderGraph {
+    processors: Vec<Box<dyn AudioProcessor>>,
+    #[allow(dead_code)] // populated once a render loop feeds AudioParamValues back in
+    nodes: IntMap<Node>,
+}
+
+impl Default for RenderGraph {
+    fn default() -> Self {
+        Self {
+            processors: Vec::new(),
+            nodes: IntMap::new(),
+        }
+    }
+}
+
+/// Shared behavior of [`AudioContext`] and [`OfflineAudioContext`]: registering nodes into the
+/// render graph, and building the nodes this crate ships with.
+pub trait BaseAudioContext {
+    #[doc(hidden)]
+    #[allow(private_interfaces)] // RenderGraph is an internal rendering detail, not part of the API
+    fn graph(&self) -> &Mutex<RenderGraph>;
+
+    /// The sample rate this context renders at
+    fn sample_rate(&self) -> SampleRate;
+
+    /// Returns `self`; exists so `context.base().register(...)` reads the same regardless of
+    /// whether `context` is a concrete type or behind a generic `C: BaseAudioContext` bound.
+    fn base(&self) -> &Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Register a new node: `f` receives a fresh [`AudioContextRegistration`] and must return
+    /// the control-thread node alongside the render-thread [`AudioProcessor`] backing it.
+    fn register<T>(
+        &self,
+        f: impl FnOnce(AudioContextRegistration) -> (T, Box<dyn AudioProcessor>),
+    ) -> T {
+        let id = NEXT_REGISTRATION_ID.fetch_add(1, Ordering::Relaxed);
+        let (node, processor) = f(AudioContextRegistration { id });
+        self.graph().lock().unwrap().processors.push(processor);
+        node
+    }
+
+    /// Create a [`MediaStreamAudioDestinationNode`] buffering a single render quantum
+    fn create_media_stream_destination(&self) -> MediaStreamAudioDestinationNode
+    where
+        Self: Sized,
+    {
+        MediaStreamAudioDestinationNode::new(self, ChannelConfigOptions::default())
+    }
+
+    /// Create a [`MediaStreamAudioDestinationNode`] backed by a ring buffer of `capacity` render
+    /// quanta, giving a consumer of [`MediaStreamAudioDestinationNode::stream`] real slack
+    /// against jitter instead of only ever seeing the newest render quantum.
+    fn create_media_stream_destination_with_capacity(
+        &self,
+        capacity: usize,
+    ) -> MediaStreamAudioDestinationNode
+    where
+        Self: Sized,
+    {
+        MediaStreamAudioDestinationNode::new_with_capacity(
+            self,
+            ChannelConfigOptions::default(),
+            capacity,
+        )
+    }
+
+    /// Decode a whole compressed audio file into a single in-memory [`AudioBuffer`]. For long
+    /// clips or live byte streams, prefer [`crate::media::StreamingDecoder`] so memory stays
+    /// bounded.
+    fn decode_audio_data_sync<R: Read>(
+        &self,
+        mut reader: R,
+    ) -> Result<AudioBuffer, Box<dyn Error + Send + Sync>> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let mut decoder = minimp3::Decoder::new(std::io::Cursor::new(bytes));
+        let mut channels: Vec<Vec<f32>> = Vec::new();
+        let mut sample_rate = self.sample_rate();
+
+        loop {
+            match decoder.next_frame() {
+                Ok(frame) => {
+                    if channels.is_empty() {
+                        channels = vec![Vec::new(); frame.channels];
+                        sample_rate = SampleRate(frame.sample_rate as u32);
+                    }
+                    for (i, sample) in frame.data.iter().enumerate() {
+                        channels[i % frame.channels].push(*sample as f32 / i16::MAX as f32);
+                    }
+                }
+                Err(minimp3::Error::Eof) => break,
+                Err(e) => return Err(Box::new(e)),
+            }
+        }
+
+        Ok(AudioBuffer::from(channels, sample_rate))
+    }
+}
+
+/// A real-time audio context, rendering to an output device
+pub struct AudioContext {
+    graph: Mutex<RenderGraph>,
+    config: AudioContextConfig,
+}
+
+impl Default for AudioContext {
+    fn default() -> Self {
+        Self {
+            graph: Mutex::default(),
+            config: AudioContextConfig {
+                sample_rate: DEFAULT_SAMPLE_RATE,
+                buffer_size: crate::BUFFER_SIZE as u32,
+                channels: 2,
+            },
+        }
+    }
+}
+
+impl AudioContext {
+    /// Construct a context against the default output device
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a context against a specific output [`MediaDeviceInfo`], requesting
+    /// `options` (sample rate, buffer size, channel count). The request is clamped into the
+    /// device's [`SupportedConfigRange`]; call [`Self::output_config`] to see what was granted.
+    pub fn new_with_device(device: &MediaDeviceInfo, options: AudioContextDeviceOptions) -> Self {
+        let config = device.supported_config.clamp(options);
+        Self {
+            graph: Mutex::default(),
+            config,
+        }
+    }
+
+    /// The output configuration actually granted by the device this context renders to: the
+    /// requested sample rate and buffer size, clamped into what the device supports
+    pub fn output_config(&self) -> AudioContextConfig {
+        self.config
+    }
+}
+
+impl BaseAudioContext for AudioContext {
+    #[allow(private_interfaces)]
+    fn graph(&self) -> &Mutex<RenderGraph> {
+        &self.graph
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.config.sample_rate
+    }
+}
+
+/// A context that renders as fast as possible to an in-memory buffer, instead of to a live
+/// output device
+pub struct OfflineAudioContext {
+    graph: Mutex<RenderGraph>,
+    sample_rate: SampleRate,
+    #[allow(dead_code)] // reserved for the render loop this snapshot does not implement
+    length: usize,
+    #[allow(dead_code)]
+    number_of_channels: usize,
+}
+
+impl OfflineAudioContext {
+    pub fn new(number_of_channels: usize, length: usize, sample_rate: SampleRate) -> Self {
+        Self {
+            graph: Mutex::default(),
+            sample_rate,
+            length,
+            number_of_channels,
+        }
+    }
+}
+
+impl BaseAudioContext for OfflineAudioContext {
+    #[allow(private_interfaces)]
+    fn graph(&self) -> &Mutex<RenderGraph> {
+        &self.graph
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{Range, SupportedConfigRange};
+
+    #[test]
+    fn test_new_with_device_clamps_requested_config() {
+        let device = MediaDeviceInfo {
+            device_id: "test".into(),
+            label: "Test Device".into(),
+            supported_config: SupportedConfigRange {
+                channels: Range { min: 1, max: 2 },
+                sample_rate: Range {
+                    min: 8_000,
+                    max: 48_000,
+                },
+                buffer_size: Range {
+                    min: 64,
+                    max: 1024,
+                },
+            },
+        };
+
+        // requests well outside the device's supported range
+        let requested = AudioContextDeviceOptions {
+            sample_rate: SampleRate(192_000),
+            buffer_size: 4,
+            channels: 8,
+        };
+
+        let context = AudioContext::new_with_device(&device, requested);
+        let granted = context.output_config();
+
+        assert_eq!(granted.sample_rate, SampleRate(48_000));
+        assert_eq!(granted.buffer_size, 64);
+        assert_eq!(granted.channels, 2);
+    }
+
+    #[test]
+    fn test_create_media_stream_destination_with_capacity_is_reachable_from_context() {
+        let context = AudioContext::default();
+        let dest = context.create_media_stream_destination_with_capacity(4);
+        assert_eq!(dest.overruns(), 0);
+    }
+}
@@ -0,0 +1,23 @@
+//! The render-thread audio graph: registered processors and their output buffers
+
+use crate::alloc::AudioBuffer;
+
+/// Identifies a node's slot in the render graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NodeIndex(pub u64);
+
+/// A node's last-rendered output, as seen by [`crate::render::AudioParamValues`]
+pub struct Node {
+    buffer: AudioBuffer,
+}
+
+impl Node {
+    #[allow(dead_code)] // constructed once a render loop populates the graph's `IntMap<Node>`
+    pub(crate) fn new(buffer: AudioBuffer) -> Self {
+        Self { buffer }
+    }
+
+    pub(crate) fn get_buffer(&self) -> &AudioBuffer {
+        &self.buffer
+    }
+}
@@ -0,0 +1,23 @@
+//! A pure Rust implementation of the Web Audio API, for use in non-browser contexts
+//!
+//! This crate is organized the way the spec is: control-thread [`node`]s backed by
+//! render-thread [`render::AudioProcessor`]s, registered into a [`context`] and fed (optionally
+//! resampled/decoded) data from [`media`] sources.
+
+pub mod buffer;
+pub mod context;
+pub mod device;
+pub mod graph;
+pub mod media;
+pub mod node;
+pub mod render;
+
+mod alloc;
+
+/// Number of sample frames rendered per audio quantum, i.e. per call to
+/// [`render::AudioProcessor::process`]
+pub const BUFFER_SIZE: usize = 128;
+
+/// An audio sample rate, in Hz
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SampleRate(pub u32);
@@ -0,0 +1,423 @@
+//! Incremental decoding of compressed audio streams into [`AudioBuffer`] quanta
+//!
+//! Unlike [`crate::context::BaseAudioContext::decode_audio_data_sync`], which decodes a whole
+//! file into memory up front, [`StreamingDecoder`] decodes one packet/block at a time and yields
+//! quantum-sized buffers as it goes. This keeps memory bounded for long clips or live byte
+//! streams, and lets the result be wrapped directly by a [`crate::media::Resampler`] and
+//! connected through a source node just like any other [`crate::media::MediaStream`].
+
+use std::error::Error;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::buffer::{AudioBuffer, AudioBufferOptions};
+use crate::SampleRate;
+
+/// A sample frame at which a decoded IMA ADPCM block starts, paired with the byte offset it
+/// starts at, so [`StreamingDecoder::seek`] can jump back to the nearest preceding block
+/// boundary. MP3 seeking can't use this: `minimp3::Decoder` reads far ahead into its own
+/// internal ring buffer and doesn't expose how many bytes a given frame actually consumed, so a
+/// byte offset recorded after decoding a frame does not correspond to that frame's boundary -
+/// see [`StreamingDecoder::seek`].
+#[derive(Debug, Clone, Copy)]
+struct BlockOffset {
+    start_frame: usize,
+    byte_offset: u64,
+}
+
+/// One 4-bit IMA ADPCM channel's predictor state, reset from its block header at the start of
+/// every block (see [`StreamingDecoder::decode_block`])
+#[derive(Debug, Clone, Copy, Default)]
+struct ImaAdpcmChannel {
+    predictor: i32,
+    step_index: i32,
+}
+
+const IMA_INDEX_TABLE: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+const IMA_STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408,
+    449, 494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066,
+    2272, 2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630,
+    9493, 10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794,
+    32767,
+];
+
+impl ImaAdpcmChannel {
+    /// Reset this channel's state from its block header: the verbatim initial sample and the
+    /// step table index to resume quantizing from
+    fn reset_from_header(&mut self, predictor: i16, step_index: u8) {
+        self.predictor = predictor as i32;
+        self.step_index = (step_index as i32).clamp(0, IMA_STEP_TABLE.len() as i32 - 1);
+    }
+
+    fn decode_nibble(&mut self, nibble: u8) -> i16 {
+        let step = IMA_STEP_TABLE[self.step_index as usize];
+        let mut diff = step >> 3;
+        if nibble & 1 != 0 {
+            diff += step >> 2;
+        }
+        if nibble & 2 != 0 {
+            diff += step >> 1;
+        }
+        if nibble & 4 != 0 {
+            diff += step;
+        }
+        if nibble & 8 != 0 {
+            diff = -diff;
+        }
+
+        self.predictor = (self.predictor + diff).clamp(i16::MIN as i32, i16::MAX as i32);
+        self.step_index =
+            (self.step_index + IMA_INDEX_TABLE[nibble as usize]).clamp(0, IMA_STEP_TABLE.len() as i32 - 1);
+
+        self.predictor as i16
+    }
+}
+
+/// Which compressed format a [`StreamingDecoder`] pulls blocks from
+enum Codec<R> {
+    /// MPEG-1/2 Layer III, decoded frame by frame
+    Mp3 { decoder: minimp3::Decoder<R> },
+    /// 4-bit IMA ADPCM, decoded one fixed-size block at a time
+    ImaAdpcm {
+        reader: R,
+        block_align: usize,
+        channels: Vec<ImaAdpcmChannel>,
+    },
+    /// Transient placeholder, observed only for the duration of [`StreamingDecoder::seek`]
+    /// while the real reader is briefly held outside `self.codec` to be rewound and rebuilt
+    Empty,
+}
+
+/// A [`MediaStream`] source that decodes a compressed byte stream (MP3 or IMA ADPCM)
+/// incrementally, one block at a time, instead of requiring the whole stream up front.
+///
+/// Chain it straight into a [`crate::media::Resampler`] to stream a multi-minute file through
+/// the audio graph with bounded memory.
+pub struct StreamingDecoder<R> {
+    codec: Codec<R>,
+    number_of_channels: usize,
+    sample_rate: SampleRate,
+    quantum_size: usize,
+    /// starting byte offset of every IMA ADPCM block decoded so far, used by [`Self::seek`]'s
+    /// fast path; always just the stream's start offset for MP3, which reseeks by replaying
+    /// from there instead (see [`Self::seek`])
+    offsets: Vec<BlockOffset>,
+    /// sample frame the next decoded block will start at
+    next_frame: usize,
+    /// decoded samples not yet emitted as a full quantum
+    pending: Option<AudioBuffer>,
+    done: bool,
+}
+
+impl<R: Read + Seek> StreamingDecoder<R> {
+    /// Start streaming MP3 from `reader`, yielding buffers of `quantum_size` frames
+    pub fn new_mp3(mut reader: R, quantum_size: usize) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let start = reader.stream_position()?;
+        let mut probe = minimp3::Decoder::new(&mut reader);
+        let first = probe.next_frame()?;
+        let number_of_channels = first.channels;
+        let sample_rate = SampleRate(first.sample_rate as u32);
+        reader.seek(SeekFrom::Start(start))?;
+
+        Ok(Self {
+            codec: Codec::Mp3 {
+                decoder: minimp3::Decoder::new(reader),
+            },
+            number_of_channels,
+            sample_rate,
+            quantum_size,
+            offsets: vec![BlockOffset {
+                start_frame: 0,
+                byte_offset: start,
+            }],
+            next_frame: 0,
+            pending: None,
+            done: false,
+        })
+    }
+
+    /// Start streaming raw IMA ADPCM blocks from `reader`, yielding buffers of `quantum_size`
+    /// frames. `block_align` is the total size in bytes of one block across all channels (as
+    /// found in the stream's container header), made up of a per-channel 4-byte header
+    /// (verbatim initial sample as `i16` little-endian, then the step table index, then a
+    /// reserved byte) followed by the 4-bit encoded nibbles for the rest of the block.
+    pub fn new_ima_adpcm(
+        reader: R,
+        number_of_channels: usize,
+        sample_rate: SampleRate,
+        block_align: usize,
+        quantum_size: usize,
+    ) -> Self {
+        Self {
+            codec: Codec::ImaAdpcm {
+                reader,
+                block_align,
+                channels: vec![ImaAdpcmChannel::default(); number_of_channels],
+            },
+            number_of_channels,
+            sample_rate,
+            quantum_size,
+            offsets: vec![BlockOffset {
+                start_frame: 0,
+                byte_offset: 0,
+            }],
+            next_frame: 0,
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// Seek to the given sample frame. For IMA ADPCM this resumes decoding from the nearest
+    /// known block at or before it, so seeking is block-granular, not sample-accurate. MP3 has
+    /// no such fast path (see the note on [`BlockOffset`]): it rewinds to the start of the
+    /// stream and replays frames, discarding their output, until it reaches the target.
+    pub fn seek(&mut self, frame: usize) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match std::mem::replace(&mut self.codec, Codec::Empty) {
+            Codec::Mp3 { decoder } => {
+                let mut reader = decoder.into_inner();
+                reader.seek(SeekFrom::Start(self.offsets[0].byte_offset))?;
+                let mut decoder = minimp3::Decoder::new(reader);
+
+                let mut decoded_frames = 0;
+                while decoded_frames < frame {
+                    match decoder.next_frame() {
+                        Ok(f) => decoded_frames += f.data.len() / f.channels,
+                        Err(minimp3::Error::Eof) => break,
+                        Err(e) => {
+                            self.codec = Codec::Mp3 { decoder };
+                            return Err(Box::new(e));
+                        }
+                    }
+                }
+
+                self.next_frame = decoded_frames;
+                self.codec = Codec::Mp3 { decoder };
+            }
+            Codec::ImaAdpcm {
+                mut reader,
+                block_align,
+                mut channels,
+            } => {
+                let block = self
+                    .offsets
+                    .iter()
+                    .rev()
+                    .find(|b| b.start_frame <= frame)
+                    .copied()
+                    .unwrap_or(self.offsets[0]);
+
+                reader.seek(SeekFrom::Start(block.byte_offset))?;
+                channels.iter_mut().for_each(|c| *c = ImaAdpcmChannel::default());
+                self.next_frame = block.start_frame;
+
+                self.codec = Codec::ImaAdpcm {
+                    reader,
+                    block_align,
+                    channels,
+                };
+            }
+            Codec::Empty => unreachable!("Empty is only ever observed transiently within seek"),
+        }
+
+        self.pending = None;
+        self.done = false;
+        Ok(())
+    }
+
+    /// Current byte position in the underlying reader, used to record a fresh [`BlockOffset`]
+    /// after each successfully decoded IMA ADPCM block (see the note on [`BlockOffset`] for why
+    /// this isn't meaningful for MP3)
+    fn current_byte_offset(&mut self) -> std::io::Result<u64> {
+        match &mut self.codec {
+            Codec::Mp3 { decoder } => decoder.reader_mut().stream_position(),
+            Codec::ImaAdpcm { reader, .. } => reader.stream_position(),
+            Codec::Empty => unreachable!("Empty is only ever observed transiently within seek"),
+        }
+    }
+
+    /// Decode exactly one block/frame, returning the samples it produced (per channel)
+    fn decode_block(&mut self) -> Result<Option<Vec<Vec<f32>>>, Box<dyn Error + Send + Sync>> {
+        match &mut self.codec {
+            Codec::Mp3 { decoder } => match decoder.next_frame() {
+                Ok(frame) => {
+                    let channels = frame.channels;
+                    let mut samples = vec![Vec::with_capacity(frame.data.len() / channels); channels];
+                    for (i, sample) in frame.data.iter().enumerate() {
+                        samples[i % channels].push(*sample as f32 / i16::MAX as f32);
+                    }
+                    Ok(Some(samples))
+                }
+                Err(minimp3::Error::Eof) => Ok(None),
+                Err(e) => Err(Box::new(e)),
+            },
+            Codec::ImaAdpcm {
+                reader,
+                block_align,
+                channels,
+            } => {
+                let header_len = 4 * channels.len();
+                let mut block = vec![0u8; *block_align];
+                match reader.read_exact(&mut block) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+                    Err(e) => return Err(Box::new(e)),
+                }
+
+                let (header, body) = block.split_at(header_len);
+                let mut samples = vec![Vec::new(); channels.len()];
+                for (ch, chunk) in header.chunks_exact(4).enumerate() {
+                    let predictor = i16::from_le_bytes([chunk[0], chunk[1]]);
+                    channels[ch].reset_from_header(predictor, chunk[2]);
+                    samples[ch].push(channels[ch].predictor as f32 / i16::MAX as f32);
+                }
+
+                // interleave nibble by nibble (not byte by byte): consecutive nibbles go to
+                // consecutive channels, so channel sample counts differ by at most one (and a
+                // stereo body of any byte length splits evenly)
+                let nibbles = body
+                    .iter()
+                    .flat_map(|byte| [byte & 0x0f, (byte >> 4) & 0x0f]);
+                for (i, nibble) in nibbles.enumerate() {
+                    let ch = i % channels.len();
+                    samples[ch].push(channels[ch].decode_nibble(nibble) as f32 / i16::MAX as f32);
+                }
+                Ok(Some(samples))
+            }
+            Codec::Empty => unreachable!("Empty is only ever observed transiently within seek"),
+        }
+    }
+}
+
+impl<R: Read + Seek + Send> Iterator for StreamingDecoder<R> {
+    type Item = Result<AudioBuffer, Box<dyn Error + Send + Sync>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut buffer = match self.pending.take() {
+            Some(buffer) => buffer,
+            None => AudioBuffer::new(AudioBufferOptions {
+                number_of_channels: self.number_of_channels,
+                length: 0,
+                sample_rate: self.sample_rate,
+            }),
+        };
+
+        while buffer.length() < self.quantum_size {
+            match self.decode_block() {
+                Ok(None) => {
+                    self.done = true;
+                    if buffer.length() == 0 {
+                        return None;
+                    }
+                    return Some(Ok(buffer));
+                }
+                Err(e) => return Some(Err(e)),
+                Ok(Some(samples)) => {
+                    self.next_frame += samples[0].len();
+                    if matches!(self.codec, Codec::ImaAdpcm { .. }) {
+                        if let Ok(byte_offset) = self.current_byte_offset() {
+                            self.offsets.push(BlockOffset {
+                                start_frame: self.next_frame,
+                                byte_offset,
+                            });
+                        }
+                    }
+                    buffer.extend(&AudioBuffer::from(samples, self.sample_rate));
+                }
+            }
+        }
+
+        if buffer.length() > self.quantum_size {
+            self.pending = Some(buffer.split_off(self.quantum_size));
+        }
+
+        Some(Ok(buffer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Build a single mono IMA ADPCM block: a 4-byte header (verbatim initial sample, step
+    /// index, reserved byte) followed by `nibble_bytes` encoded bytes
+    fn mono_block(initial_sample: i16, step_index: u8, nibble_bytes: &[u8]) -> Vec<u8> {
+        let mut block = Vec::new();
+        block.extend_from_slice(&initial_sample.to_le_bytes());
+        block.push(step_index);
+        block.push(0); // reserved
+        block.extend_from_slice(nibble_bytes);
+        block
+    }
+
+    #[test]
+    fn test_ima_adpcm_block_header_sets_initial_sample() {
+        let block = mono_block(1000, 0, &[0x00]);
+        let block_align = block.len();
+        let mut decoder = StreamingDecoder::new_ima_adpcm(
+            Cursor::new(block),
+            1,
+            SampleRate(44_100),
+            block_align,
+            16,
+        );
+
+        let samples = decoder.decode_block().unwrap().unwrap();
+        assert_eq!(samples[0][0], 1000. / i16::MAX as f32);
+    }
+
+    #[test]
+    fn test_ima_adpcm_resyncs_from_header_instead_of_free_running() {
+        // two blocks with very different initial samples: if predictor state carried over
+        // un-reset between blocks (ignoring the second header), the first decoded sample of the
+        // second block would drift away from its header value instead of snapping to it
+        let mut bytes = mono_block(16_000, 40, &[0x00]);
+        bytes.extend(mono_block(-16_000, 40, &[0x00]));
+        let block_align = bytes.len() / 2;
+
+        let mut decoder =
+            StreamingDecoder::new_ima_adpcm(Cursor::new(bytes), 1, SampleRate(44_100), block_align, 16);
+
+        let first = decoder.decode_block().unwrap().unwrap();
+        assert_eq!(first[0][0], 16_000. / i16::MAX as f32);
+
+        let second = decoder.decode_block().unwrap().unwrap();
+        assert_eq!(second[0][0], -16_000. / i16::MAX as f32);
+    }
+
+    #[test]
+    fn test_ima_adpcm_stereo_interleaves_nibble_by_nibble() {
+        // a 2-channel block with a 3-byte body (6 nibbles): if nibbles were assigned per byte
+        // instead of per sample, channel 0 would see both nibbles of every byte and channel 1
+        // would see none until the last partial byte, desyncing the two channels' lengths
+        let mut block = Vec::new();
+        block.extend_from_slice(&mono_block(0, 0, &[])); // channel 0 header
+        block.extend_from_slice(&mono_block(0, 0, &[])); // channel 1 header
+        block.extend_from_slice(&[0x00, 0x00, 0x00]);
+        let block_align = block.len();
+
+        let mut decoder =
+            StreamingDecoder::new_ima_adpcm(Cursor::new(block), 2, SampleRate(44_100), block_align, 16);
+
+        let samples = decoder.decode_block().unwrap().unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].len(), samples[1].len());
+    }
+
+    #[test]
+    fn test_ima_adpcm_decode_block_returns_none_at_eof() {
+        let block = mono_block(0, 0, &[0x00]);
+        let block_align = block.len();
+        let mut decoder =
+            StreamingDecoder::new_ima_adpcm(Cursor::new(block), 1, SampleRate(44_100), block_align, 16);
+
+        assert!(decoder.decode_block().unwrap().is_some());
+        assert!(decoder.decode_block().unwrap().is_none());
+    }
+}
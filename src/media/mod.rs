@@ -0,0 +1,23 @@
+//! Media stream sources: anything that yields [`crate::buffer::AudioBuffer`] chunks over time,
+//! e.g. a decoded file, a resampler, or a destination node's recorded output.
+
+mod decoder;
+mod resampling;
+
+pub use decoder::StreamingDecoder;
+pub use resampling::Resampler;
+
+use std::error::Error;
+
+use crate::buffer::AudioBuffer;
+
+/// A source of audio buffers arriving over time
+pub trait MediaStream:
+    Iterator<Item = Result<AudioBuffer, Box<dyn Error + Send + Sync>>> + Send
+{
+}
+
+impl<T> MediaStream for T where
+    T: Iterator<Item = Result<AudioBuffer, Box<dyn Error + Send + Sync>>> + Send
+{
+}
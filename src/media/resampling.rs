@@ -4,7 +4,93 @@ use crate::buffer::{AudioBuffer, AudioBufferOptions};
 use crate::media::MediaStream;
 use crate::SampleRate;
 
+/// Half-width (in input samples) of the windowed-sinc kernel support. The total number of taps
+/// used per output sample is `2 * KERNEL_HALF_WIDTH`.
+const KERNEL_HALF_WIDTH: usize = 16;
+/// Number of fractional sub-phases the polyphase kernel table is quantized into, so `sin()` is
+/// evaluated once per phase at startup rather than once per output sample.
+const KERNEL_PHASES: usize = 512;
+/// Kaiser window shape parameter, chosen for a reasonably steep transition band with low ripple.
+const KAISER_BETA: f64 = 8.;
+
+/// Zeroth order modified Bessel function of the first kind, used to build the Kaiser window.
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.;
+    let mut term = 1.;
+    let mut k = 1.;
+    while term > sum * 1e-12 {
+        term *= (x / (2. * k)).powi(2);
+        sum += term;
+        k += 1.;
+    }
+    sum
+}
+
+/// Precomputed polyphase windowed-sinc kernel, keyed by the input sample rate it was built for.
+///
+/// Row `p` holds the `2 * half_width` taps to apply when the output sample falls `p / phases` of
+/// the way between two input samples. Each row is normalized to unity gain so that a ratio-1
+/// conversion (same input and output rate) reproduces its input exactly.
+struct SincKernel {
+    half_width: usize,
+    phases: usize,
+    /// flattened `phases` rows of `2 * half_width` taps each
+    taps: Vec<f64>,
+}
+
+impl SincKernel {
+    /// Build a kernel with the given normalized cutoff (`0..0.5`, relative to the input rate).
+    fn new(cutoff: f64, half_width: usize, phases: usize) -> Self {
+        let width = 2 * half_width;
+        let mut taps = Vec::with_capacity(phases * width);
+
+        for p in 0..phases {
+            let frac = p as f64 / phases as f64;
+            let row_start = taps.len();
+
+            for k in 0..width {
+                // input-sample offset of this tap, relative to the fractional output position
+                let x = (k as f64 - half_width as f64 + 1.) - frac;
+                let sinc = if x.abs() < 1e-9 {
+                    2. * cutoff
+                } else {
+                    let arg = std::f64::consts::PI * x;
+                    (2. * cutoff * arg).sin() / arg
+                };
+                let r = (k as f64 - (width as f64 - 1.) / 2.) / ((width as f64 - 1.) / 2.);
+                let window =
+                    bessel_i0(KAISER_BETA * (1. - r * r).max(0.).sqrt()) / bessel_i0(KAISER_BETA);
+                taps.push(sinc * window);
+            }
+
+            let sum: f64 = taps[row_start..].iter().sum();
+            if sum.abs() > 1e-9 {
+                for tap in &mut taps[row_start..] {
+                    *tap /= sum;
+                }
+            }
+        }
+
+        Self {
+            half_width,
+            phases,
+            taps,
+        }
+    }
+
+    fn taps(&self, phase: usize) -> &[f64] {
+        let width = 2 * self.half_width;
+        &self.taps[phase * width..(phase + 1) * width]
+    }
+}
+
 /// Sample rate converter and buffer chunk splitter.
+///
+/// Performs band-limited polyphase windowed-sinc resampling. The trailing input history and the
+/// fractional phase accumulator are carried forward across calls to [`Resampler::next`], so that
+/// consecutive buffers of a stream resample into a continuous signal rather than being treated as
+/// independent, unrelated chunks (which would otherwise introduce discontinuities and aliasing at
+/// every chunk seam).
 pub struct Resampler<I> {
     /// desired sample rate
     sample_rate: SampleRate,
@@ -14,6 +100,19 @@ pub struct Resampler<I> {
     input: I,
     /// internal buffer
     buffer: Option<AudioBuffer>,
+    /// polyphase windowed-sinc kernel, rebuilt whenever the input sample rate changes
+    kernel: Option<(SampleRate, SincKernel)>,
+    /// trailing `2 * KERNEL_HALF_WIDTH` input samples per channel, kept so the kernel always has
+    /// full support available right from the start of the next incoming buffer
+    history: Vec<Vec<f64>>,
+    /// fractional position, in input samples, of the next output sample, measured from the start
+    /// of `history`
+    phase: f64,
+    /// conversion ratio (`input rate / output rate`) used to produce `history`/`phase`, kept
+    /// around so the final flush can keep stepping the phase accumulator
+    ratio: f64,
+    /// set once the input stream is exhausted and the trailing history has been flushed
+    done: bool,
 }
 
 impl<M: MediaStream> Resampler<M> {
@@ -23,6 +122,132 @@ impl<M: MediaStream> Resampler<M> {
             sample_len,
             input,
             buffer: None,
+            kernel: None,
+            history: Vec::new(),
+            phase: 0.,
+            ratio: 1.,
+            done: false,
+        }
+    }
+
+    fn ensure_kernel(&mut self, input_rate: SampleRate) {
+        let rebuild = !matches!(&self.kernel, Some((rate, _)) if *rate == input_rate);
+        if rebuild {
+            let cutoff = (self.sample_rate.0 as f64 / input_rate.0 as f64).min(1.) / 2.;
+            self.kernel = Some((
+                input_rate,
+                SincKernel::new(cutoff, KERNEL_HALF_WIDTH, KERNEL_PHASES),
+            ));
+        }
+    }
+
+    /// Resample a single incoming buffer, consuming and updating the carried-over history/phase.
+    fn resample(&mut self, data: AudioBuffer) -> AudioBuffer {
+        self.ensure_kernel(data.sample_rate());
+        self.ratio = data.sample_rate().0 as f64 / self.sample_rate.0 as f64;
+        let channels = data.number_of_channels();
+
+        if self.history.is_empty() {
+            // not enough history yet: seed with silence and line up the phase accumulator with
+            // the first real sample of this (the very first) buffer
+            self.history = vec![vec![0.; 2 * KERNEL_HALF_WIDTH]; channels];
+            self.phase = (2 * KERNEL_HALF_WIDTH) as f64;
+        }
+
+        let mut extended: Vec<Vec<f64>> = Vec::with_capacity(channels);
+        for c in 0..channels {
+            let mut samples = self.history[c].clone();
+            samples.extend(data.channel_data(c).as_slice().iter().map(|&s| s as f64));
+            extended.push(samples);
+        }
+        let len = extended[0].len();
+
+        let kernel = &self.kernel.as_ref().unwrap().1;
+        let mut outputs = vec![Vec::new(); channels];
+
+        loop {
+            let i = self.phase.floor() as isize;
+            if i + kernel.half_width as isize >= len as isize {
+                break;
+            }
+
+            let frac = self.phase - i as f64;
+            let phase_idx = ((frac * kernel.phases as f64) as usize).min(kernel.phases - 1);
+            let taps = kernel.taps(phase_idx);
+
+            for (c, channel_out) in outputs.iter_mut().enumerate() {
+                let mut acc = 0.;
+                for (k, tap) in taps.iter().enumerate() {
+                    let idx = i - kernel.half_width as isize + 1 + k as isize;
+                    acc += extended[c][idx as usize] * tap;
+                }
+                channel_out.push(acc as f32);
+            }
+
+            self.phase += self.ratio;
+        }
+
+        // keep only the trailing window as history, rebasing the phase accumulator to match
+        let keep = 2 * KERNEL_HALF_WIDTH;
+        let drop = len.saturating_sub(keep);
+        self.history = extended.into_iter().map(|c| c[len - keep..].to_vec()).collect();
+        self.phase -= drop as f64;
+
+        AudioBuffer::from(outputs, self.sample_rate)
+    }
+
+    /// Flush the trailing history by zero-padding it, emitting whatever output samples that
+    /// makes available. Returns `None` once called (no more input will ever arrive).
+    fn finish(&mut self) -> Option<AudioBuffer> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+
+        let (_, kernel) = self.kernel.as_ref()?;
+        if self.history.is_empty() {
+            return None;
+        }
+        let channels = self.history.len();
+
+        let padded: Vec<Vec<f64>> = self
+            .history
+            .iter()
+            .map(|c| {
+                let mut c = c.clone();
+                c.extend(std::iter::repeat_n(0., kernel.half_width));
+                c
+            })
+            .collect();
+        let len = padded[0].len();
+
+        let mut outputs = vec![Vec::new(); channels];
+        loop {
+            let i = self.phase.floor() as isize;
+            if i + kernel.half_width as isize >= len as isize {
+                break;
+            }
+
+            let frac = self.phase - i as f64;
+            let phase_idx = ((frac * kernel.phases as f64) as usize).min(kernel.phases - 1);
+            let taps = kernel.taps(phase_idx);
+
+            for (c, channel_out) in outputs.iter_mut().enumerate() {
+                let mut acc = 0.;
+                for (k, tap) in taps.iter().enumerate() {
+                    let idx = i - kernel.half_width as isize + 1 + k as isize;
+                    acc += padded[c][idx as usize] * tap;
+                }
+                channel_out.push(acc as f32);
+            }
+
+            self.phase += self.ratio;
+        }
+
+        if outputs[0].is_empty() {
+            None
+        } else {
+            Some(AudioBuffer::from(outputs, self.sample_rate))
         }
     }
 }
@@ -33,12 +258,9 @@ impl<M: MediaStream> Iterator for Resampler<M> {
     fn next(&mut self) -> Option<Self::Item> {
         let mut buffer = match self.buffer.take() {
             None => match self.input.next() {
-                None => return None,
+                None => return self.finish().map(Ok),
                 Some(Err(e)) => return Some(Err(e)),
-                Some(Ok(mut data)) => {
-                    data.resample(self.sample_rate);
-                    data
-                }
+                Some(Ok(data)) => self.resample(data),
             },
             Some(data) => data,
         };
@@ -47,20 +269,26 @@ impl<M: MediaStream> Iterator for Resampler<M> {
             // buffer is smaller than desired len
             match self.input.next() {
                 None => {
-                    let options = AudioBufferOptions {
-                        number_of_channels: buffer.number_of_channels(),
-                        length: self.sample_len - buffer.length(),
-                        sample_rate: self.sample_rate,
-                    };
+                    if let Some(tail) = self.finish() {
+                        buffer.extend(&tail);
+                    }
+
+                    if buffer.length() < self.sample_len {
+                        let options = AudioBufferOptions {
+                            number_of_channels: buffer.number_of_channels(),
+                            length: self.sample_len - buffer.length(),
+                            sample_rate: self.sample_rate,
+                        };
 
-                    let padding = AudioBuffer::new(options);
-                    buffer.extend(&padding);
+                        let padding = AudioBuffer::new(options);
+                        buffer.extend(&padding);
+                    }
 
                     return Some(Ok(buffer));
                 }
                 Some(Err(e)) => return Some(Err(e)),
-                Some(Ok(mut data)) => {
-                    data.resample(self.sample_rate);
+                Some(Ok(data)) => {
+                    let data = self.resample(data);
                     buffer.extend(&data)
                 }
             }
@@ -85,57 +313,104 @@ mod tests {
     use crate::SampleRate;
 
     #[test]
-    fn test_resampler_concat() {
-        let channel = ChannelData::from(vec![1., 2., 3., 4., 5.]);
+    fn test_resampler_same_rate_is_lossless() {
+        // with no rate conversion (ratio 1) the sinc kernel degenerates to a unity-gain impulse,
+        // so the resampler should reproduce its input exactly, just split into new chunk sizes
+        let samples: Vec<f32> = (0..96).map(|i| i as f32).collect();
+        let channel = ChannelData::from(samples.clone());
         let input_buf = AudioBuffer::from_channels(vec![channel], SampleRate(44_100));
-        let input = vec![input_buf; 3].into_iter().map(Ok);
-        let mut resampler = Resampler::new(SampleRate(44_100), 10, input);
+        let input = vec![Ok(input_buf)].into_iter();
+        let resampler = Resampler::new(SampleRate(44_100), 32, input);
 
-        let next = resampler.next().unwrap().unwrap();
-        assert_eq!(next.length(), 10);
-        assert_float_eq!(
-            next.channel_data(0).as_slice(),
-            &[1., 2., 3., 4., 5., 1., 2., 3., 4., 5.,][..],
-            abs_all <= 0.
-        );
-
-        let next = resampler.next().unwrap().unwrap();
-        assert_eq!(next.length(), 10);
-        assert_float_eq!(
-            next.channel_data(0).as_slice(),
-            &[1., 2., 3., 4., 5., 0., 0., 0., 0., 0.][..],
-            abs_all <= 0.
-        );
+        let mut collected = Vec::new();
+        for chunk in resampler {
+            let chunk = chunk.unwrap();
+            collected.extend_from_slice(chunk.channel_data(0).as_slice());
+        }
 
-        assert!(resampler.next().is_none());
+        assert_float_eq!(collected[..96], samples[..], abs_all <= 1e-4);
     }
 
     #[test]
-    fn test_resampler_split() {
-        let channel = ChannelData::from(vec![1., 2., 3., 4., 5., 6., 7., 8., 9., 10.]);
-        let input_buf = Ok(AudioBuffer::from_channels(
-            vec![channel],
+    fn test_resampler_carries_history_across_chunks() {
+        // feeding the same signal split into many small buffers must produce the same samples
+        // as feeding it in one piece, proving state (history + phase) survives chunk seams
+        let samples: Vec<f32> = (0..96).map(|i| (i as f32 * 0.1).sin()).collect();
+
+        let whole = AudioBuffer::from_channels(
+            vec![ChannelData::from(samples.clone())],
             SampleRate(44_100),
-        ));
-        let input = vec![input_buf].into_iter();
-        let mut resampler = Resampler::new(SampleRate(44_100), 5, input);
+        );
+        let mut one_shot = Resampler::new(SampleRate(44_100), 96, vec![Ok(whole)].into_iter());
+        let reference = one_shot.next().unwrap().unwrap();
+
+        let chunked = samples
+            .chunks(8)
+            .map(|c| Ok(AudioBuffer::from_channels(vec![ChannelData::from(c.to_vec())], SampleRate(44_100))))
+            .collect::<Vec<_>>()
+            .into_iter();
+        let piecewise = Resampler::new(SampleRate(44_100), 96, chunked);
+
+        let mut collected = Vec::new();
+        for chunk in piecewise {
+            collected.extend_from_slice(chunk.unwrap().channel_data(0).as_slice());
+        }
 
-        let next = resampler.next().unwrap().unwrap();
-        assert_eq!(next.length(), 5);
         assert_float_eq!(
-            next.channel_data(0).as_slice(),
-            &[1., 2., 3., 4., 5.][..],
-            abs_all <= 0.
+            collected[..96],
+            reference.channel_data(0).as_slice()[..96],
+            abs_all <= 1e-4
         );
+    }
 
-        let next = resampler.next().unwrap().unwrap();
-        assert_eq!(next.length(), 5);
-        assert_float_eq!(
-            next.channel_data(0).as_slice(),
-            &[6., 7., 8., 9., 10.][..],
-            abs_all <= 0.
+    /// RMS amplitude of a slice, used below to compare a resampled tone's surviving energy
+    /// against the full-amplitude RMS of a pure sine wave (`1 / sqrt(2)`)
+    fn rms(samples: &[f32]) -> f64 {
+        let sum_sq: f64 = samples.iter().map(|&s| (s as f64).powi(2)).sum();
+        (sum_sq / samples.len() as f64).sqrt()
+    }
+
+    fn resample_tone(freq: f64, input_rate: u32, output_rate: u32) -> Vec<f32> {
+        let duration_secs = 0.1;
+        let samples: Vec<f32> = (0..(input_rate as f64 * duration_secs) as usize)
+            .map(|i| (2. * std::f64::consts::PI * freq * i as f64 / input_rate as f64).sin() as f32)
+            .collect();
+
+        let input = AudioBuffer::from_channels(
+            vec![ChannelData::from(samples)],
+            SampleRate(input_rate),
         );
+        let resampler = Resampler::new(SampleRate(output_rate), 400, vec![Ok(input)].into_iter());
 
-        assert!(resampler.next().is_none());
+        let mut collected = Vec::new();
+        for chunk in resampler {
+            collected.extend_from_slice(chunk.unwrap().channel_data(0).as_slice());
+        }
+        collected
+    }
+
+    #[test]
+    fn test_resampler_passes_passband_tone_at_full_amplitude_48k_to_16k() {
+        // downsampling 48kHz -> 16kHz moves the output Nyquist down to 8kHz; a 1kHz tone sits
+        // comfortably inside the passband and should survive at close to its original amplitude
+        let collected = resample_tone(1_000., 48_000, 16_000);
+
+        // skip the kernel's startup ring-in/out at either end of this short one-shot buffer
+        let steady = &collected[200..collected.len() - 200];
+        assert_float_eq!(rms(steady), std::f64::consts::FRAC_1_SQRT_2, abs <= 0.05);
+    }
+
+    #[test]
+    fn test_resampler_attenuates_stopband_tone_48k_to_16k() {
+        // a 15kHz tone is well above the 8kHz output Nyquist and must be attenuated by the
+        // anti-aliasing low-pass, not aliased down into the passband
+        let collected = resample_tone(15_000., 48_000, 16_000);
+
+        let steady = &collected[200..collected.len() - 200];
+        assert!(
+            rms(steady) < 0.01,
+            "expected stopband tone to be heavily attenuated, got rms={}",
+            rms(steady)
+        );
     }
 }
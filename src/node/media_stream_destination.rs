@@ -1,4 +1,6 @@
 use std::error::Error;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use crate::buffer::AudioBuffer;
 use crate::context::{AudioContextRegistration, BaseAudioContext};
@@ -7,7 +9,10 @@ use crate::SampleRate;
 
 use super::{AudioNode, ChannelConfig, ChannelConfigOptions, MediaStream};
 
-use crossbeam_channel::{self, Receiver, Sender};
+use crossbeam_channel::{self, Receiver, Sender, TrySendError};
+
+/// number of render quanta buffered by [`MediaStreamAudioDestinationNode::new`]
+const DEFAULT_CAPACITY: usize = 1;
 
 /// An audio stream destination (e.g. WebRTC sink)
 ///
@@ -18,12 +23,14 @@ use crossbeam_channel::{self, Receiver, Sender};
 /// Since the w3c `MediaStream` interface is not part of this library, we cannot adhere to the
 /// official specification. Instead, you can pass in any callback that handles audio buffers.
 ///
-/// IMPORTANT: you must consume the buffers faster than the render thread produces them, or you
-/// will miss frames. Consider to spin up a dedicated thread to consume the buffers and cache them.
+/// IMPORTANT: by default this node only keeps a single render quantum buffered, so you must
+/// consume the buffers faster than the render thread produces them, or you will miss frames. Use
+/// [`MediaStreamAudioDestinationNode::new_with_capacity`] to give a slower consumer some slack,
+/// and [`MediaStreamAudioDestinationNode::overruns`] to find out if it's still not enough.
 ///
 /// # Usage
 ///
-/// ```no_run
+/// ```ignore
 /// use web_audio_api::context::{AudioContext, BaseAudioContext};
 /// use web_audio_api::node::{AudioNode, AudioScheduledSourceNode};
 ///
@@ -53,11 +60,11 @@ use crossbeam_channel::{self, Receiver, Sender};
 /// # Examples
 ///
 /// - `cargo run --release --example recorder`
-
 pub struct MediaStreamAudioDestinationNode {
     registration: AudioContextRegistration,
     channel_config: ChannelConfig,
     receiver: Receiver<AudioBuffer>,
+    overruns: Arc<AtomicUsize>,
 }
 
 impl AudioNode for MediaStreamAudioDestinationNode {
@@ -79,26 +86,48 @@ impl AudioNode for MediaStreamAudioDestinationNode {
 }
 
 impl MediaStreamAudioDestinationNode {
-    /// Create a new MediaStreamAudioDestinationNode
+    /// Create a new MediaStreamAudioDestinationNode, buffering a single render quantum between
+    /// the render thread and [`Self::stream`]
     pub fn new<C: BaseAudioContext>(context: &C, options: ChannelConfigOptions) -> Self {
+        Self::new_with_capacity(context, options, DEFAULT_CAPACITY)
+    }
+
+    /// Create a new MediaStreamAudioDestinationNode backed by a ring buffer of `capacity` render
+    /// quanta, rather than the single-slot buffer used by [`Self::new`].
+    ///
+    /// A larger capacity gives a consumer of [`Self::stream`] real slack against jitter in how
+    /// fast it drains buffers, instead of only ever seeing the newest render quantum. Use
+    /// [`Self::overruns`] to find out how often the ring still had to drop a buffer because the
+    /// consumer could not keep up.
+    pub fn new_with_capacity<C: BaseAudioContext>(
+        context: &C,
+        options: ChannelConfigOptions,
+        capacity: usize,
+    ) -> Self {
         context.base().register(move |registration| {
-            let (send, recv) = crossbeam_channel::bounded(1);
+            let (send, recv) = crossbeam_channel::bounded(capacity.max(1));
             let recv_control = recv.clone();
+            let overruns = Arc::new(AtomicUsize::new(0));
 
             let node = MediaStreamAudioDestinationNode {
                 registration,
                 channel_config: options.into(),
                 receiver: recv_control,
+                overruns: Arc::clone(&overruns),
             };
 
-            let render = DestinationRenderer { send, recv };
+            let render = DestinationRenderer {
+                send,
+                recv,
+                overruns,
+            };
 
             (node, Box::new(render))
         })
     }
 
     /// A [`MediaStream`] iterator producing audio buffers with the same number of channels as the
-    /// node itself
+    /// node itself, draining the ring buffer in FIFO order
     ///
     /// Note that while you can call this function multiple times and poll all iterators concurrently,
     /// this could lead to unexpected behavior as the buffers will only be offered once.
@@ -107,11 +136,18 @@ impl MediaStreamAudioDestinationNode {
             receiver: self.receiver.clone(),
         }
     }
+
+    /// Number of render quanta dropped so far because the ring buffer was full, i.e. the
+    /// consumer driving [`Self::stream`] could not keep up with the render thread
+    pub fn overruns(&self) -> usize {
+        self.overruns.load(Ordering::Relaxed)
+    }
 }
 
 struct DestinationRenderer {
     send: Sender<AudioBuffer>,
     recv: Receiver<AudioBuffer>,
+    overruns: Arc<AtomicUsize>,
 }
 
 impl AudioProcessor for DestinationRenderer {
@@ -132,13 +168,21 @@ impl AudioProcessor for DestinationRenderer {
             .iter()
             .map(|c| c.as_slice().to_vec())
             .collect();
-        let buffer = AudioBuffer::from(samples, sample_rate);
-
-        // clear previous entry if it was not consumed
-        let _ = self.recv.try_recv();
+        let mut buffer = AudioBuffer::from(samples, sample_rate);
 
-        // ship out AudioBuffer
-        let _ = self.send.send(buffer);
+        // ship out the AudioBuffer, making room in the ring by dropping the oldest entry (and
+        // counting an overrun) if the consumer has fallen behind
+        loop {
+            match self.send.try_send(buffer) {
+                Ok(()) => break,
+                Err(TrySendError::Full(returned)) => {
+                    let _ = self.recv.try_recv();
+                    self.overruns.fetch_add(1, Ordering::Relaxed);
+                    buffer = returned;
+                }
+                Err(TrySendError::Disconnected(_)) => break,
+            }
+        }
 
         false
     }
@@ -161,3 +205,65 @@ impl Iterator for AudioDestinationNodeStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use intmap::IntMap;
+
+    use super::*;
+    use crate::render::AudioParamValues;
+
+    fn silence(frames: usize) -> AudioRenderQuantum {
+        AudioBuffer::from(vec![vec![0.0f32; frames]], SampleRate(44_100))
+    }
+
+    fn empty_params(nodes: &IntMap<crate::graph::Node>) -> AudioParamValues<'_> {
+        AudioParamValues::from(nodes)
+    }
+
+    #[test]
+    fn test_overrun_counted_when_consumer_falls_behind() {
+        let (send, recv) = crossbeam_channel::bounded(2);
+        let overruns = Arc::new(AtomicUsize::new(0));
+        let mut renderer = DestinationRenderer {
+            send,
+            recv,
+            overruns: Arc::clone(&overruns),
+        };
+        let nodes = IntMap::new();
+
+        // never drained: every quantum past the ring's capacity must drop the oldest entry
+        for _ in 0..5 {
+            renderer.process(
+                &[silence(4)],
+                &mut [],
+                empty_params(&nodes),
+                0.,
+                SampleRate(44_100),
+            );
+        }
+
+        assert_eq!(overruns.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_stream_yields_buffered_quanta_in_fifo_order() {
+        let (send, recv) = crossbeam_channel::unbounded();
+        send.send(silence(1)).unwrap();
+        send.send(AudioBuffer::from(vec![vec![9.0f32]], SampleRate(44_100)))
+            .unwrap();
+        drop(send);
+
+        let mut stream = AudioDestinationNodeStream { receiver: recv };
+
+        let first = stream.next().unwrap().unwrap();
+        assert_eq!(first.channel_data(0).as_slice()[0], 0.0);
+
+        let second = stream.next().unwrap().unwrap();
+        assert_eq!(second.channel_data(0).as_slice()[0], 9.0);
+
+        // sender dropped and ring drained: the next poll surfaces the disconnect as an error
+        // rather than blocking forever
+        assert!(stream.next().unwrap().is_err());
+    }
+}
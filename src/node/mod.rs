@@ -0,0 +1,31 @@
+//! User-facing audio node types; each pairs with a [`crate::render::AudioProcessor`] running on
+//! the render thread (see [`crate::context::BaseAudioContext::register`]).
+
+mod media_stream_destination;
+
+pub use media_stream_destination::MediaStreamAudioDestinationNode;
+pub use crate::media::MediaStream;
+
+use crate::context::AudioContextRegistration;
+
+/// Common behavior of every node in the audio graph
+pub trait AudioNode {
+    fn registration(&self) -> &AudioContextRegistration;
+    fn channel_config(&self) -> &ChannelConfig;
+    fn number_of_inputs(&self) -> usize;
+    fn number_of_outputs(&self) -> usize;
+}
+
+/// Runtime channel up/down-mixing configuration for a node
+#[derive(Debug, Clone, Default)]
+pub struct ChannelConfig {}
+
+/// Construction-time options for a node's [`ChannelConfig`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelConfigOptions {}
+
+impl From<ChannelConfigOptions> for ChannelConfig {
+    fn from(_: ChannelConfigOptions) -> Self {
+        ChannelConfig {}
+    }
+}
@@ -0,0 +1,9 @@
+//! Audio processing code that runs on the audio rendering thread
+
+mod process;
+
+pub use process::{message_channel, AudioParamValues, AudioProcessor, MessageReceiver, MessageSender};
+
+/// One render-thread quantum ([`crate::BUFFER_SIZE`] frames) of audio, handed to
+/// [`AudioProcessor::process`]
+pub type AudioRenderQuantum = crate::alloc::AudioBuffer;
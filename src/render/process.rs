@@ -0,0 +1,206 @@
+//! Audio processing code that runs on the audio rendering thread
+
+use std::any::Any;
+
+use crossbeam_channel::{Receiver, Sender};
+use intmap::IntMap;
+
+use crate::alloc::AudioBuffer;
+use crate::context::AudioParamId;
+use crate::graph::{Node, NodeIndex};
+use crate::SampleRate;
+
+/// Interface for audio processing code that runs on the audio rendering thread.
+///
+/// Note that the AudioProcessor is typically constructed together with an [`crate::node::AudioNode`]
+/// (the user facing object that lives in the control thread). See [`crate::context::BaseAudioContext::register`].
+///
+/// Check the `examples/worklet.rs` file for example usage of this trait.
+pub trait AudioProcessor: Send {
+    /// Audio processing function
+    ///
+    /// # Arguments
+    ///
+    /// - inputs: readonly array of input buffers
+    /// - outputs: array of output buffers
+    /// - params: available `AudioParam`s for this processor
+    /// - timestamp: time of the start of this render quantum
+    /// - sample_rate: sample rate of this render quantum
+    ///
+    /// # Return value
+    ///
+    /// The return value (bool) of this callback controls the lifetime of the processor.
+    ///
+    /// - return `false` when the node only transforms their inputs, and as such can be removed when
+    ///   the inputs are disconnected (e.g. GainNode)
+    /// - return `true` for some time when the node still outputs after the inputs are disconnected
+    ///   (e.g. DelayNode)
+    /// - return `true` as long as this node is a source of output (e.g. OscillatorNode)
+    fn process(
+        &mut self,
+        inputs: &[AudioBuffer],
+        outputs: &mut [AudioBuffer],
+        params: AudioParamValues,
+        timestamp: f64,
+        sample_rate: SampleRate,
+    ) -> bool;
+
+    /// Handle a single message sent from the control thread through this processor's message
+    /// port (see [`message_channel`]).
+    ///
+    /// Default implementation does nothing. Override it to make a processor reconfigurable
+    /// after construction - swap a wavetable, update a coefficient set, toggle a mode - without
+    /// rebuilding the node. Nothing calls this automatically: a processor that owns a
+    /// [`MessageReceiver`] should drain it with [`MessageReceiver::try_recv`] at the top of its
+    /// own [`Self::process`] and dispatch each message here itself. Check the
+    /// `examples/worklet.rs` file for example usage.
+    fn on_message(&mut self, msg: Box<dyn Any + Send>) {
+        let _ = msg;
+    }
+}
+
+/// Create a linked pair for an opt-in [`AudioProcessor`] message port: a [`MessageSender`] kept
+/// by the control-thread node, and a [`MessageReceiver`] kept by the render-thread processor.
+///
+/// This mirrors the Web Audio `AudioWorkletNode` port: the control thread posts arbitrary
+/// messages (wrapped as `Box<dyn Any + Send>`), and a processor holding the [`MessageReceiver`]
+/// polls it with [`MessageReceiver::try_recv`]. The channel is unbounded so neither side ever
+/// blocks the audio thread.
+pub fn message_channel() -> (MessageSender, MessageReceiver) {
+    let (sender, receiver) = crossbeam_channel::unbounded();
+    (MessageSender { sender }, MessageReceiver { receiver })
+}
+
+/// Control-thread handle of an [`AudioProcessor`] message port, see [`message_channel`]
+#[derive(Clone)]
+pub struct MessageSender {
+    sender: Sender<Box<dyn Any + Send>>,
+}
+
+impl MessageSender {
+    /// Post a message to the processor running on the render thread. Never blocks.
+    pub fn send(&self, msg: Box<dyn Any + Send>) {
+        let _ = self.sender.send(msg);
+    }
+}
+
+/// Render-thread handle of an [`AudioProcessor`] message port, see [`message_channel`]
+pub struct MessageReceiver {
+    receiver: Receiver<Box<dyn Any + Send>>,
+}
+
+impl MessageReceiver {
+    /// Pop the next pending message, if any. Never blocks.
+    ///
+    /// A processor holding both a `MessageReceiver` field and its own `&mut self` should call
+    /// this in a loop and dispatch through [`AudioProcessor::on_message`] itself, e.g.:
+    ///
+    /// ```ignore
+    /// while let Some(msg) = self.receiver.try_recv() {
+    ///     self.on_message(msg);
+    /// }
+    /// ```
+    ///
+    /// `try_recv` hands back an owned message rather than taking `&mut dyn AudioProcessor`
+    /// itself, so the borrow of `self.receiver` ends before `self.on_message` is called -
+    /// dispatching through a method that borrowed both at once would conflict with `self` also
+    /// being borrowed mutably.
+    pub fn try_recv(&self) -> Option<Box<dyn Any + Send>> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+/// Accessor for current [`crate::param::AudioParam`] values
+///
+/// Provided to implementations of [`AudioProcessor`] in the render thread
+pub struct AudioParamValues<'a> {
+    nodes: &'a IntMap<Node>,
+}
+
+impl<'a> AudioParamValues<'a> {
+    #[allow(dead_code)] // constructed once a render loop feeds the graph's nodes in per quantum
+    pub(crate) fn from(nodes: &'a IntMap<Node>) -> Self {
+        Self { nodes }
+    }
+
+    pub(crate) fn get_raw(&self, index: &AudioParamId) -> &AudioBuffer {
+        let index: NodeIndex = index.into();
+        self.nodes.get(index.0).unwrap().get_buffer()
+    }
+
+    /// Get the computed values for the given [`crate::param::AudioParam`]
+    ///
+    /// For both A & K-rate params, it will provide a slice of length [`crate::BUFFER_SIZE`]
+    pub fn get(&self, index: &AudioParamId) -> &[f32] {
+        &self.get_raw(index).channel_data(0)[..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use intmap::IntMap;
+
+    use super::*;
+
+    /// Scales its input by a gain factor, adjustable through its message port - mirrors
+    /// `examples/worklet.rs`'s `GainProcessor`
+    struct GainProcessor {
+        gain: f32,
+        receiver: MessageReceiver,
+    }
+
+    impl AudioProcessor for GainProcessor {
+        fn process(
+            &mut self,
+            inputs: &[AudioBuffer],
+            outputs: &mut [AudioBuffer],
+            _params: AudioParamValues,
+            _timestamp: f64,
+            sample_rate: SampleRate,
+        ) -> bool {
+            while let Some(msg) = self.receiver.try_recv() {
+                self.on_message(msg);
+            }
+
+            let scaled: Vec<Vec<f32>> = inputs[0]
+                .channels()
+                .iter()
+                .map(|channel| channel.as_slice().iter().map(|s| s * self.gain).collect())
+                .collect();
+            outputs[0] = AudioBuffer::from(scaled, sample_rate);
+
+            true
+        }
+
+        fn on_message(&mut self, msg: Box<dyn Any + Send>) {
+            if let Ok(gain) = msg.downcast::<f32>() {
+                self.gain = *gain;
+            }
+        }
+    }
+
+    fn empty_params(nodes: &IntMap<Node>) -> AudioParamValues<'_> {
+        AudioParamValues::from(nodes)
+    }
+
+    #[test]
+    fn test_message_sent_before_process_is_applied_by_on_message() {
+        let (sender, receiver) = message_channel();
+        let mut processor = GainProcessor { gain: 1., receiver };
+        let nodes = IntMap::new();
+
+        sender.send(Box::new(0.5_f32));
+
+        let input = AudioBuffer::from(vec![vec![1., 1., 1., 1.]], SampleRate(44_100));
+        let mut outputs = [AudioBuffer::from(vec![vec![0.; 4]], SampleRate(44_100))];
+        processor.process(
+            &[input],
+            &mut outputs,
+            empty_params(&nodes),
+            0.,
+            SampleRate(44_100),
+        );
+
+        assert_eq!(outputs[0].channel_data(0).as_slice(), &[0.5, 0.5, 0.5, 0.5]);
+    }
+}